@@ -3,13 +3,16 @@ I will consider one-dimensional offline bin packing problem
 One-dimensional, as in objects and containers only have a single dimension
 Offline, as in all object dimensions are known before-hand 
 */
-use rand::{thread_rng, Rng};
+use rand::{thread_rng, Rng, SeedableRng};
+use rand::rngs::StdRng;
 use std::fs;
 use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
 use serde_json::Result;
 use std::time::Instant;
 use std::cmp;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
 
 
 #[derive(Clone, Serialize, Deserialize, Default)]
@@ -32,13 +35,37 @@ struct ProblemResult {
 
     time_us_best_case: u128,
     time_us_worst_case: u128,
-    time_us_avg_case: f32
+    time_us_avg_case: f32,
+
+    // 2-D shelf-packing mode only
+    area_utilization_best_case: Option<f32>,
+    area_utilization_worst_case: Option<f32>,
+    area_utilization_avg_case: Option<f32>,
+
+    // Total packed item value, as opposed to container count
+    objective_best_case: f32,
+    objective_worst_case: f32,
+    objective_avg_case: f32,
+
+    // Seed used to drive the Generator, for reproducing a reported case
+    seed: u64,
+
+    // Only populated when the post-solve consolidation pass ran
+    containers_after_consolidation_best_case: Option<f32>,
+    containers_after_consolidation_worst_case: Option<f32>,
+    containers_after_consolidation_avg_case: Option<f32>,
+
+    time_us_consolidation_best_case: Option<u128>,
+    time_us_consolidation_worst_case: Option<u128>,
+    time_us_consolidation_avg_case: Option<f32>
 }
 
 
 #[derive(Clone)]
 struct Item {
-    size: u32
+    size: u32,
+    // For the value-maximization solvers
+    value: Option<u32>
 }
 
 #[derive(Clone)]
@@ -74,7 +101,32 @@ struct Settings {
     item_size_min: u32,
     item_size_max: u32,
     item_limit: u32,
-    container_size: u32
+    container_size: u32,
+
+    // Both set switches to 2-D shelf packing; `item_size_min`/`item_size_max`
+    // are then reused as the rectangle width/height range.
+    #[serde(default)]
+    sheet_width: Option<u32>,
+    #[serde(default)]
+    sheet_height: Option<u32>,
+    #[serde(default)]
+    padding: Option<u32>,
+
+    // Both set enables random item values, for the value-maximization solvers
+    #[serde(default)]
+    value_min: Option<u32>,
+    #[serde(default)]
+    value_max: Option<u32>,
+
+    // If unset, a seed is drawn from entropy and reported in `ProblemResult.seed`
+    #[serde(default)]
+    seed: Option<u64>,
+
+    // Both set enables the post-solve consolidation pass (see `consolidate_underfilled_bins`)
+    #[serde(default)]
+    target_fill_ratio: Option<f32>,
+    #[serde(default)]
+    max_passes: Option<u32>
 }
 
 struct GeneratorResults {
@@ -84,10 +136,25 @@ struct GeneratorResults {
 
 #[derive(Clone)]
 struct Generator {
-    settings: Settings
+    settings: Settings,
+    seed: u64,
+    rng: RefCell<StdRng>
 }
 
 impl Generator {
+    // Seeds the Generator's RNG from `settings.seed` if present, otherwise draws
+    // a seed from entropy; either way the chosen seed is kept on the struct so it
+    // can be reported back in `ProblemResult.seed` for exact reproduction.
+    fn new(settings: Settings) -> Self {
+        let seed: u64 = settings.seed.unwrap_or_else(|| thread_rng().gen());
+
+        Generator {
+            settings: settings,
+            seed: seed,
+            rng: RefCell::new(StdRng::seed_from_u64(seed))
+        }
+    }
+
     fn generate(&self) -> GeneratorResults {
         let mut current_size = 0;
         let mut containers = 0;
@@ -98,7 +165,7 @@ impl Generator {
                 containers += 1;
             }
 
-            let mut size: u32 = rand::thread_rng().gen_range(
+            let mut size: u32 = self.rng.borrow_mut().gen_range(
                 self.settings.item_size_min..self.settings.item_size_max
             );
 
@@ -111,19 +178,156 @@ impl Generator {
             }
 
             items.push(Item {
-                size: size 
+                size: size,
+                value: self.generate_value()
             });
 
             current_size = (current_size + size) % self.settings.container_size;
         }
 
-        items.shuffle(&mut thread_rng());
+        items.shuffle(&mut *self.rng.borrow_mut());
 
         return GeneratorResults {
             items: items,
             optimal_container_count: containers
         };
     }
+
+    // Draws a random value in `value_min..value_max` when both are configured,
+    // for the value-maximization solvers; otherwise items carry no value.
+    fn generate_value(&self) -> Option<u32> {
+        return match (self.settings.value_min, self.settings.value_max) {
+            (Some(value_min), Some(value_max)) => Some(self.rng.borrow_mut().gen_range(value_min..value_max)),
+            _ => None
+        };
+    }
+
+    // Generates `item_limit` rectangles with width and height drawn independently
+    // from `item_size_min..item_size_max`, for the 2-D shelf-packing mode.
+    fn generate_rects(&self) -> Vec<RectItem> {
+        let mut rects: Vec<RectItem> = Vec::new();
+
+        for _ in 0..self.settings.item_limit {
+            let w: u32 = self.rng.borrow_mut().gen_range(
+                self.settings.item_size_min..self.settings.item_size_max
+            );
+            let h: u32 = self.rng.borrow_mut().gen_range(
+                self.settings.item_size_min..self.settings.item_size_max
+            );
+
+            rects.push(RectItem { w: w, h: h });
+        }
+
+        return rects;
+    }
+}
+
+#[derive(Clone)]
+struct RectItem {
+    w: u32,
+    h: u32
+}
+
+struct Shelf {
+    y_offset: u32,
+    height: u32,
+    x_cursor: u32
+}
+
+struct Sheet {
+    width: u32,
+    height: u32,
+    shelves: Vec<Shelf>,
+    used_area: u32
+}
+
+impl Sheet {
+    fn new(width: u32, height: u32) -> Self {
+        Sheet {
+            width: width,
+            height: height,
+            shelves: Vec::new(),
+            used_area: 0
+        }
+    }
+
+    fn area_utilization(&self) -> f32 {
+        return self.used_area as f32 / (self.width * self.height) as f32;
+    }
+}
+
+// Sorts items by decreasing height and packs them onto shelves: a shelf tracks an
+// x-cursor and a fixed height, and an item is placed on the first shelf it fits on
+// (`x_cursor + w <= width` and `h <= shelf_height`); if none fit, a new shelf is
+// opened at the running y-offset, or a new sheet if the sheet itself is full.
+// `padding` is added around every placed rectangle, as in the packos crate.
+struct ShelfPacker {
+    width: u32,
+    height: u32,
+    padding: u32
+}
+
+impl ShelfPacker {
+    fn pack(&self, mut items: Vec<RectItem>) -> Vec<Sheet> {
+        items.sort_unstable_by_key(|item| item.h);
+        items.reverse();
+
+        let mut sheets: Vec<Sheet> = vec![Sheet::new(self.width, self.height)];
+
+        for item in items {
+            let w: u32 = item.w + 2 * self.padding;
+            let h: u32 = item.h + 2 * self.padding;
+
+            let mut placed: bool = false;
+            for sheet in sheets.iter_mut() {
+                for shelf in sheet.shelves.iter_mut() {
+                    if shelf.x_cursor + w <= sheet.width && h <= shelf.height {
+                        shelf.x_cursor += w;
+                        sheet.used_area += item.w * item.h;
+                        placed = true;
+                        break;
+                    }
+                }
+                if placed {
+                    break;
+                }
+
+                let next_y_offset: u32 = sheet.shelves.iter()
+                    .map(|shelf| shelf.y_offset + shelf.height)
+                    .max()
+                    .unwrap_or(0);
+
+                if next_y_offset + h <= sheet.height {
+                    sheet.shelves.push(Shelf {
+                        y_offset: next_y_offset,
+                        height: h,
+                        x_cursor: w
+                    });
+                    sheet.used_area += item.w * item.h;
+                    placed = true;
+                    break;
+                }
+            }
+
+            if !placed {
+                // If it can't fit into an empty sheet, panic. Won't be able to provide a solution
+                if w > self.width || h > self.height {
+                    panic!("An item won't fit into an empty sheet!");
+                }
+
+                let mut new_sheet: Sheet = Sheet::new(self.width, self.height);
+                new_sheet.shelves.push(Shelf {
+                    y_offset: 0,
+                    height: h,
+                    x_cursor: w
+                });
+                new_sheet.used_area += item.w * item.h;
+                sheets.push(new_sheet);
+            }
+        }
+
+        return sheets;
+    }
 }
 
 trait Solver {
@@ -133,6 +337,11 @@ trait Solver {
     fn new_container(&self) -> Container {
         return Container::new(self.get_settings().container_size);
     }
+
+    // Optional post-solve repacking stage, run once after `solve`. No-op by default.
+    fn consolidate(&self, result: Vec<Container>) -> Vec<Container> {
+        return result;
+    }
 }
 
 struct SolverNextFit {
@@ -150,6 +359,10 @@ impl Solver for SolverNextFit {
         return "Next Fit".to_string();
     }
 
+    fn consolidate(&self, result: Vec<Container>) -> Vec<Container> {
+        return consolidate_underfilled_bins(self.get_settings(), result);
+    }
+
     fn solve(&self, input: Vec<Item>) -> Vec<Container> {
         let mut results: Vec<Container> = Vec::new();
         let mut last_index: usize = 0;
@@ -196,6 +409,10 @@ impl Solver for SolverFirstFit {
         return "First Fit".to_string();
     }
 
+    fn consolidate(&self, result: Vec<Container>) -> Vec<Container> {
+        return consolidate_underfilled_bins(self.get_settings(), result);
+    }
+
     fn solve(&self, input: Vec<Item>) -> Vec<Container> {
         let mut results: Vec<Container> = Vec::new();
         let mut containers_count: usize = 1;
@@ -234,6 +451,237 @@ impl Solver for SolverFirstFit {
     }
 }
 
+struct SolverBestFit {
+    settings: Settings
+}
+
+impl Solver for SolverBestFit {
+    /* Best-Fit (BF) keeps all bins open, indexed by remaining capacity in a
+    BTreeMap<u32, Vec<usize>>. Each new item is placed into the open bin with the
+    smallest remaining capacity that still fits it, found in O(log bins) via
+    map.range(size..).next() instead of scanning every bin. */
+    fn get_settings(&self) -> &Settings {
+        return &self.settings;
+    }
+
+    fn get_name(&self) -> String{
+        return "Best Fit".to_string();
+    }
+
+    fn consolidate(&self, result: Vec<Container>) -> Vec<Container> {
+        return consolidate_underfilled_bins(self.get_settings(), result);
+    }
+
+    fn solve(&self, input: Vec<Item>) -> Vec<Container> {
+        let mut results: Vec<Container> = Vec::new();
+        let mut remaining_to_bins: BTreeMap<u32, Vec<usize>> = BTreeMap::new();
+
+        for item in input {
+            let chosen_remaining: Option<u32> = remaining_to_bins.range(item.size..)
+                .next()
+                .map(|(&remaining, _)| remaining);
+
+            let index: usize = pick_or_open_bin(&mut results, &mut remaining_to_bins, chosen_remaining, self);
+
+            place_and_reindex(&mut results, &mut remaining_to_bins, index, item);
+        }
+
+        return results;
+    }
+}
+
+struct SolverWorstFit {
+    settings: Settings
+}
+
+impl Solver for SolverWorstFit {
+    /* Worst-Fit (WF) keeps all bins open, indexed by remaining capacity in a
+    BTreeMap<u32, Vec<usize>>. Each new item is placed into the open bin with the
+    largest remaining capacity, equivalent to a max-heap keyed on free space, found
+    in O(log bins) via map.iter().next_back(). */
+    fn get_settings(&self) -> &Settings {
+        return &self.settings;
+    }
+
+    fn get_name(&self) -> String{
+        return "Worst Fit".to_string();
+    }
+
+    fn consolidate(&self, result: Vec<Container>) -> Vec<Container> {
+        return consolidate_underfilled_bins(self.get_settings(), result);
+    }
+
+    fn solve(&self, input: Vec<Item>) -> Vec<Container> {
+        let mut results: Vec<Container> = Vec::new();
+        let mut remaining_to_bins: BTreeMap<u32, Vec<usize>> = BTreeMap::new();
+
+        for item in input {
+            let chosen_remaining: Option<u32> = remaining_to_bins.iter()
+                .next_back()
+                .filter(|(&remaining, _)| remaining >= item.size)
+                .map(|(&remaining, _)| remaining);
+
+            let index: usize = pick_or_open_bin(&mut results, &mut remaining_to_bins, chosen_remaining, self);
+
+            place_and_reindex(&mut results, &mut remaining_to_bins, index, item);
+        }
+
+        return results;
+    }
+}
+
+// Pops a bin index out of `remaining_to_bins` under `chosen_remaining`, or opens a
+// fresh container when no open bin qualifies (used by both Best-Fit and Worst-Fit).
+fn pick_or_open_bin(
+    results: &mut Vec<Container>,
+    remaining_to_bins: &mut BTreeMap<u32, Vec<usize>>,
+    chosen_remaining: Option<u32>,
+    solver: &dyn Solver
+) -> usize {
+    match chosen_remaining {
+        Some(remaining) => {
+            let bins = remaining_to_bins.get_mut(&remaining).unwrap();
+            let index = bins.pop().unwrap();
+            if bins.is_empty() {
+                remaining_to_bins.remove(&remaining);
+            }
+            return index;
+        },
+        None => {
+            results.push(solver.new_container());
+            return results.len() - 1;
+        }
+    }
+}
+
+// Adds `item` to the container at `index` and reinserts the bin under its new
+// remaining-capacity key, so the map always reflects the current state of every bin.
+fn place_and_reindex(
+    results: &mut Vec<Container>,
+    remaining_to_bins: &mut BTreeMap<u32, Vec<usize>>,
+    index: usize,
+    item: Item
+) {
+    let container: &mut Container = results.get_mut(index).unwrap();
+    let rejected_item: Option<Item> = container.add(item);
+
+    if !rejected_item.is_none() {
+        panic!("An item won't fit into an empty container!");
+    }
+
+    let new_remaining: u32 = container.size - container.total;
+    remaining_to_bins.entry(new_remaining).or_insert_with(Vec::new).push(index);
+}
+
+struct SolverKnapsackGreedy {
+    settings: Settings
+}
+
+impl Solver for SolverKnapsackGreedy {
+    /* Greedy-by-value-density fills a single fixed-capacity container: items are
+    sorted by `value / size` descending, and each is added if it still fits,
+    skipped otherwise, until the container has no more room. This optimizes
+    packed value rather than minimizing container count. */
+    fn get_settings(&self) -> &Settings {
+        return &self.settings;
+    }
+
+    fn get_name(&self) -> String{
+        return "Knapsack Greedy".to_string();
+    }
+
+    fn solve(&self, mut input: Vec<Item>) -> Vec<Container> {
+        input.sort_unstable_by(|a, b| {
+            let density_a = a.value.unwrap_or(0) as f32 / a.size as f32;
+            let density_b = b.value.unwrap_or(0) as f32 / b.size as f32;
+            // A zero-size item (legal when `item_size_min == 0`) with a zero or
+            // missing value divides 0/0 to NaN, which `partial_cmp` can't order
+            // (a positive value over zero size gives +inf, which orders first);
+            // treat a NaN as neither denser nor
+            // sparser than its counterpart rather than panicking.
+            density_b.partial_cmp(&density_a).unwrap_or(cmp::Ordering::Equal)
+        });
+
+        let mut container: Container = self.new_container();
+
+        for item in input {
+            if container.total == container.size {
+                break;
+            }
+
+            container.add(item);
+        }
+
+        return vec![container];
+    }
+}
+
+// Post-solve consolidation: while `target_fill_ratio`/`max_passes` are both
+// configured, each pass sorts the open containers by `total/size` ascending,
+// empties every bin below `target_fill_ratio` and reinserts its items via
+// Best-Fit into the remaining bins (opening a new one only if none fit). The
+// pass loop stops early once a pass fails to reduce the container count.
+fn consolidate_underfilled_bins(settings: &Settings, containers: Vec<Container>) -> Vec<Container> {
+    let (target_fill_ratio, max_passes) = match (settings.target_fill_ratio, settings.max_passes) {
+        (Some(target_fill_ratio), Some(max_passes)) => (target_fill_ratio, max_passes),
+        _ => return containers
+    };
+
+    let mut containers = containers;
+
+    for _ in 0..max_passes {
+        let count_before_pass: usize = containers.len();
+
+        containers.sort_unstable_by(|a, b| {
+            let fill_a = a.total as f32 / a.size as f32;
+            let fill_b = b.total as f32 / b.size as f32;
+            fill_a.partial_cmp(&fill_b).unwrap()
+        });
+
+        let mut reclaimed_items: Vec<Item> = Vec::new();
+        let mut kept: Vec<Container> = Vec::new();
+
+        for container in containers {
+            let fill_ratio: f32 = container.total as f32 / container.size as f32;
+            if fill_ratio < target_fill_ratio {
+                reclaimed_items.extend(container.items);
+            } else {
+                kept.push(container);
+            }
+        }
+
+        if reclaimed_items.is_empty() {
+            containers = kept;
+            break;
+        }
+
+        let mut results: Vec<Container> = kept;
+        let mut remaining_to_bins: BTreeMap<u32, Vec<usize>> = BTreeMap::new();
+        for (index, container) in results.iter().enumerate() {
+            remaining_to_bins.entry(container.size - container.total).or_insert_with(Vec::new).push(index);
+        }
+
+        let best_fit = SolverBestFit { settings: settings.clone() };
+        for item in reclaimed_items {
+            let chosen_remaining: Option<u32> = remaining_to_bins.range(item.size..)
+                .next()
+                .map(|(&remaining, _)| remaining);
+
+            let index: usize = pick_or_open_bin(&mut results, &mut remaining_to_bins, chosen_remaining, &best_fit);
+            place_and_reindex(&mut results, &mut remaining_to_bins, index, item);
+        }
+
+        let pass_freed_no_bins: bool = results.len() >= count_before_pass;
+        containers = results;
+
+        if pass_freed_no_bins {
+            break;
+        }
+    }
+
+    return containers;
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 struct SolverListItem {
     id: String,
@@ -247,10 +695,17 @@ struct ProgramInput {
     iterations: u32
 }
 
+fn consolidation_enabled(settings: &Settings) -> bool {
+    return settings.target_fill_ratio.is_some() && settings.max_passes.is_some();
+}
+
 fn generate_solver(string: String, settings: Settings) -> Option<Box<dyn Solver>> {
     match string.as_str() {
         "Next Fit" => Some(Box::new(SolverNextFit {settings: settings})),
         "First Fit" => Some(Box::new(SolverFirstFit {settings: settings})),
+        "Best Fit" => Some(Box::new(SolverBestFit {settings: settings})),
+        "Worst Fit" => Some(Box::new(SolverWorstFit {settings: settings})),
+        "Knapsack Greedy" => Some(Box::new(SolverKnapsackGreedy {settings: settings})),
         _ => None
     }
 }
@@ -274,11 +729,84 @@ fn main() {
     }
     
     for (settings_n, settings) in program_input.settings.iter().enumerate() {
-        let generator: Generator = Generator { 
-            settings: settings.clone()
-        };
+        let generator: Generator = Generator::new(settings.clone());
+
+        // A settings block with both sheet dimensions set runs the 2-D shelf
+        // packer instead of the 1-D solvers, writing a single result per block.
+        if let (Some(sheet_width), Some(sheet_height)) = (settings.sheet_width, settings.sheet_height) {
+            let packer = ShelfPacker {
+                width: sheet_width,
+                height: sheet_height,
+                padding: settings.padding.unwrap_or(0)
+            };
+
+            let result_i = settings_n * solvers_length;
+            results[result_i] = ProblemResult {
+                solver_name: "Shelf Packer".to_string(),
+                solver_sorted: false,
+
+                item_size_min: settings.item_size_min,
+                item_size_max: settings.item_size_max,
+                item_limit: settings.item_limit,
+
+                container_size: settings.container_size,
+                iterations: 0,
+                optimal_solutions_found: 0,
+
+                quality_best_case: 0.0,
+                quality_worst_case: 0.0,
+                quality_avg_case: 0.0,
+
+                time_us_best_case: u128::MAX,
+                time_us_worst_case: 0,
+                time_us_avg_case: 0.0,
+
+                area_utilization_best_case: Some(0.0),
+                area_utilization_worst_case: Some(f32::MAX),
+                area_utilization_avg_case: Some(0.0),
+
+                objective_best_case: 0.0,
+                objective_worst_case: 0.0,
+                objective_avg_case: 0.0,
+
+                seed: generator.seed,
+
+                containers_after_consolidation_best_case: None,
+                containers_after_consolidation_worst_case: None,
+                containers_after_consolidation_avg_case: None,
+
+                time_us_consolidation_best_case: None,
+                time_us_consolidation_worst_case: None,
+                time_us_consolidation_avg_case: None
+            };
+
+            for _ in 0..program_input.iterations {
+                let rects: Vec<RectItem> = generator.generate_rects();
+
+                let now = Instant::now();
+                let sheets: Vec<Sheet> = packer.pack(rects);
+                let elapsed_us = now.elapsed().as_micros();
+
+                let utilization: f32 = sheets.iter().map(|sheet| sheet.area_utilization()).sum::<f32>() / sheets.len() as f32;
+
+                results[result_i].iterations += 1;
+                results[result_i].time_us_avg_case += (elapsed_us as f32 - results[result_i].time_us_avg_case) / results[result_i].iterations as f32;
+                results[result_i].time_us_worst_case = cmp::max(results[result_i].time_us_worst_case, elapsed_us);
+                results[result_i].time_us_best_case = cmp::min(results[result_i].time_us_best_case, elapsed_us);
+
+                let avg_so_far = results[result_i].area_utilization_avg_case.unwrap();
+                results[result_i].area_utilization_avg_case = Some(avg_so_far + (utilization - avg_so_far) / results[result_i].iterations as f32);
+                results[result_i].area_utilization_best_case = Some(f32::max(results[result_i].area_utilization_best_case.unwrap(), utilization));
+                results[result_i].area_utilization_worst_case = Some(f32::min(results[result_i].area_utilization_worst_case.unwrap(), utilization));
+
+                println!("Shelf Packer - {:} sheets, {:.2}% utilization, {:} us", sheets.len(), utilization * 100.0, elapsed_us);
+            }
+
+            continue;
+        }
+
         let mut solvers: Vec<Box<dyn Solver>> = Vec::new();
-    
+
         for _ in 0..program_input.iterations {
             let generator_results: GeneratorResults = generator.generate();
             let items: Vec<Item> = generator_results.items;
@@ -311,7 +839,25 @@ fn main() {
     
                         time_us_best_case: u128::MAX,
                         time_us_worst_case: 0,
-                        time_us_avg_case: 0.0
+                        time_us_avg_case: 0.0,
+
+                        area_utilization_best_case: None,
+                        area_utilization_worst_case: None,
+                        area_utilization_avg_case: None,
+
+                        objective_best_case: 0.0,
+                        objective_worst_case: f32::MAX,
+                        objective_avg_case: 0.0,
+
+                        seed: generator.seed,
+
+                        containers_after_consolidation_best_case: consolidation_enabled(settings).then(|| f32::MAX),
+                        containers_after_consolidation_worst_case: consolidation_enabled(settings).then(|| 0.0),
+                        containers_after_consolidation_avg_case: consolidation_enabled(settings).then(|| 0.0),
+
+                        time_us_consolidation_best_case: consolidation_enabled(settings).then(|| u128::MAX),
+                        time_us_consolidation_worst_case: consolidation_enabled(settings).then(|| 0),
+                        time_us_consolidation_avg_case: consolidation_enabled(settings).then(|| 0.0)
                     };
                 }
 
@@ -339,6 +885,32 @@ fn main() {
                 results[result_i].time_us_worst_case = cmp::max(results[result_i].time_us_worst_case, elapsed_us);
                 results[result_i].time_us_best_case = cmp::min(results[result_i].time_us_best_case, elapsed_us);
 
+                // The selected items themselves live on `result`, like every other solver's
+                // solution; only the aggregate value is kept across iterations below.
+                let objective: f32 = result.iter().flat_map(|c| c.items.iter()).map(|i| i.value.unwrap_or(0) as f32).sum();
+
+                results[result_i].objective_avg_case += (objective - results[result_i].objective_avg_case) / results[result_i].iterations as f32;
+                results[result_i].objective_best_case = f32::max(results[result_i].objective_best_case, objective);
+                results[result_i].objective_worst_case = f32::min(results[result_i].objective_worst_case, objective);
+
+                if consolidation_enabled(settings) {
+                    let consolidation_now = Instant::now();
+                    let consolidated: Vec<Container> = solver.consolidate(result.clone());
+                    let consolidation_elapsed_us = consolidation_now.elapsed().as_micros();
+
+                    let containers_after: f32 = consolidated.len() as f32;
+                    let iterations = results[result_i].iterations as f32;
+
+                    let avg_so_far = results[result_i].containers_after_consolidation_avg_case.unwrap();
+                    results[result_i].containers_after_consolidation_avg_case = Some(avg_so_far + (containers_after - avg_so_far) / iterations);
+                    results[result_i].containers_after_consolidation_best_case = Some(f32::min(results[result_i].containers_after_consolidation_best_case.unwrap(), containers_after));
+                    results[result_i].containers_after_consolidation_worst_case = Some(f32::max(results[result_i].containers_after_consolidation_worst_case.unwrap(), containers_after));
+
+                    let time_avg_so_far = results[result_i].time_us_consolidation_avg_case.unwrap();
+                    results[result_i].time_us_consolidation_avg_case = Some(time_avg_so_far + (consolidation_elapsed_us as f32 - time_avg_so_far) / iterations);
+                    results[result_i].time_us_consolidation_best_case = Some(cmp::min(results[result_i].time_us_consolidation_best_case.unwrap(), consolidation_elapsed_us));
+                    results[result_i].time_us_consolidation_worst_case = Some(cmp::max(results[result_i].time_us_consolidation_worst_case.unwrap(), consolidation_elapsed_us));
+                }
 
                 println!("Results for {:} {:} - {:} containers, {:} us", if sorted {"Desc-sorted"} else {"Unsorted"}, solver.get_name(), result.len(), elapsed_us);
             }
@@ -348,9 +920,183 @@ fn main() {
         
     }
 
+    // Settings blocks that run in 2-D shelf-packing mode only ever fill the first
+    // of their `solvers_length` preallocated slots (see the `continue` above);
+    // drop the rest so untouched `ProblemResult::default()` entries don't get
+    // serialized as bogus zeroed-out results.
+    let results: Vec<ProblemResult> = results.into_iter().filter(|r| !r.solver_name.is_empty()).collect();
+
     //let final_results = results.into_iter().flatten().collect();
     let slice_string_in_json_format = serde_json::to_string(&results);
     println!("{:}", slice_string_in_json_format.unwrap());
 
-    
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base_settings(container_size: u32) -> Settings {
+        Settings {
+            item_size_min: 1,
+            item_size_max: 2,
+            item_limit: 1,
+            container_size: container_size,
+            sheet_width: None,
+            sheet_height: None,
+            padding: None,
+            value_min: None,
+            value_max: None,
+            seed: None,
+            target_fill_ratio: None,
+            max_passes: None
+        }
+    }
+
+    // Opens three bins with remaining capacity 2, 3 and 6 respectively, so a
+    // final size-2 item can go to any of them and the two solvers are forced
+    // to disagree on which one.
+    fn three_open_bins() -> Vec<Item> {
+        return vec![
+            Item { size: 8, value: None },
+            Item { size: 7, value: None },
+            Item { size: 4, value: None }
+        ];
+    }
+
+    #[test]
+    fn best_fit_joins_the_tightest_qualifying_bin() {
+        let solver = SolverBestFit { settings: base_settings(10) };
+        let mut input = three_open_bins();
+        input.push(Item { size: 2, value: None });
+
+        let result = solver.solve(input);
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].total, 10);
+        assert_eq!(result[1].total, 7);
+        assert_eq!(result[2].total, 4);
+    }
+
+    #[test]
+    fn worst_fit_joins_the_loosest_qualifying_bin() {
+        let solver = SolverWorstFit { settings: base_settings(10) };
+        let mut input = three_open_bins();
+        input.push(Item { size: 2, value: None });
+
+        let result = solver.solve(input);
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].total, 8);
+        assert_eq!(result[1].total, 7);
+        assert_eq!(result[2].total, 6);
+    }
+
+    #[test]
+    fn shelf_packer_does_not_double_place_an_item_across_sheets() {
+        // Regression test: an item placed on a new shelf used to keep being
+        // scanned against later sheets, getting counted into `used_area` twice.
+        let packer = ShelfPacker { width: 20, height: 30, padding: 0 };
+        let rects = vec![
+            RectItem { w: 20, h: 20 },
+            RectItem { w: 15, h: 15 },
+            RectItem { w: 5, h: 10 }
+        ];
+
+        let sheets = packer.pack(rects);
+
+        let total_used_area: u32 = sheets.iter().map(|sheet| sheet.used_area).sum();
+        assert_eq!(total_used_area, 20 * 20 + 15 * 15 + 5 * 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "An item won't fit into an empty sheet!")]
+    fn shelf_packer_panics_on_an_item_larger_than_the_sheet() {
+        let packer = ShelfPacker { width: 20, height: 20, padding: 0 };
+
+        packer.pack(vec![RectItem { w: 25, h: 25 }]);
+    }
+
+    #[test]
+    fn knapsack_greedy_prefers_denser_items() {
+        let solver = SolverKnapsackGreedy { settings: base_settings(5) };
+        let input = vec![
+            Item { size: 5, value: Some(10) },
+            Item { size: 5, value: Some(50) }
+        ];
+
+        let result = solver.solve(input);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].items.len(), 1);
+        assert_eq!(result[0].items[0].value, Some(50));
+    }
+
+    #[test]
+    fn knapsack_greedy_does_not_panic_on_zero_size_items() {
+        // Regression test: size-0 items (legal when `item_size_min == 0`) used to
+        // divide to a NaN density and panic inside `sort_unstable_by`.
+        let solver = SolverKnapsackGreedy { settings: base_settings(5) };
+        let input = vec![
+            Item { size: 0, value: Some(0) },
+            Item { size: 5, value: Some(10) }
+        ];
+
+        let result = solver.solve(input);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].total, 5);
+    }
+
+    #[test]
+    fn same_seed_generates_identical_instances() {
+        let mut settings = base_settings(50);
+        settings.item_size_min = 1;
+        settings.item_size_max = 10;
+        settings.item_limit = 20;
+        settings.seed = Some(42);
+
+        let sizes_a: Vec<u32> = Generator::new(settings.clone()).generate().items.iter().map(|i| i.size).collect();
+        let sizes_b: Vec<u32> = Generator::new(settings.clone()).generate().items.iter().map(|i| i.size).collect();
+
+        assert_eq!(sizes_a, sizes_b);
+    }
+
+    fn container_with(size: u32, item_sizes: &[u32]) -> Container {
+        let mut container = Container::new(size);
+        for &item_size in item_sizes {
+            container.add(Item { size: item_size, value: None });
+        }
+        return container;
+    }
+
+    #[test]
+    fn consolidation_merges_underfilled_bins_and_shrinks_container_count() {
+        let mut settings = base_settings(10);
+        settings.target_fill_ratio = Some(0.5);
+        settings.max_passes = Some(5);
+
+        let containers = vec![
+            container_with(10, &[2]),
+            container_with(10, &[3]),
+            container_with(10, &[9])
+        ];
+
+        let result = consolidate_underfilled_bins(&settings, containers);
+
+        let total_packed: u32 = result.iter().map(|c| c.total).sum();
+        assert_eq!(total_packed, 14);
+        assert!(result.len() < 3);
+    }
+
+    #[test]
+    fn consolidation_is_a_no_op_without_matching_settings() {
+        let settings = base_settings(10);
+        let containers = vec![container_with(10, &[2]), container_with(10, &[3])];
+
+        let result = consolidate_underfilled_bins(&settings, containers.clone());
+
+        assert_eq!(result.len(), containers.len());
+    }
 }